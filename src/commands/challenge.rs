@@ -0,0 +1,226 @@
+// ----- `challenge` subcommand (also the bare-invocation default) -----
+
+use std::io::{self, Read};
+
+use crate::codec::Encoding;
+use crate::digest::Digest;
+use crate::secret;
+
+fn usage() -> &'static str {
+    "ykchalresp challenge [-1|-2] [-x] [-s] [--in-enc ENC] [--out-enc ENC]\n\
+                          [--digest sha1|sha256|blake3] [--out-len N] [challenge]\n\
+    ykchalresp challenge [-1|-2] --init-vault\n\n\
+    -1        use slot 1 (default: slot 2)\n\
+    -2        use slot 2\n\
+    -x        challenge and response are hex-encoded (shorthand for\n\
+              --in-enc hex --out-enc hex)\n\
+    -s        simulate in software (no hardware)\n\
+    --in-enc ENC   encoding of the challenge: raw|hex|base64|base32 (default: raw)\n\
+    --out-enc ENC  encoding of the printed response:\n\
+                   hex|modhex|base64|base32 (default: modhex)\n\
+    --digest DIGEST   simulation-only keyed hash: sha1|sha256|blake3\n\
+                      (default: sha1; hardware is always HMAC-SHA1)\n\
+    --out-len N       simulation output length in bytes for --digest\n\
+                      blake3 (any length) or sha256 (1-32, truncates the\n\
+                      HMAC-SHA256 output); default: 32; sha1 is fixed at 20\n\
+    --init-vault   encrypt the slot's existing plaintext secret into\n\
+                   slotN.key.enc under a passphrase, delete the plaintext\n\
+                   slotN.key if one was found, then exit\n\
+    If no challenge is provided, read from stdin.\n\
+    Default: use a real YubiKey directly via the Rust 'yubikey-hmac-otp' crate.\n\
+    Simulation (-s): compute a keyed hash using a secret loaded from:\n\
+      env:  YKCHALRESP_SLOT1_KEY / YKCHALRESP_SLOT2_KEY (hex)\n\
+      file: ~/.config/ykchalresp/slot1.key or slot2.key (hex)\n\
+      vault: ~/.config/ykchalresp/slot1.key.enc or slot2.key.enc, unlocked with\n\
+             YKCHALRESP_PASSPHRASE or an interactive prompt\n"
+}
+
+pub(crate) fn run(args: Vec<String>) {
+    let mut slot: u8 = 2; // default slot 2 to match common usage
+    let mut hex_mode = false;
+    let mut simulate = false;
+    let mut in_enc: Option<Encoding> = None;
+    let mut out_enc: Option<Encoding> = None;
+    let mut init_vault = false;
+    let mut digest = Digest::Sha1;
+    let mut out_len: Option<usize> = None;
+    let mut challenge_arg: Option<String> = None;
+
+    let mut args = args.into_iter();
+    while let Some(a) = args.next() {
+        match a.as_str() {
+            "-1" => slot = 1,
+            "-2" => slot = 2,
+            "-x" => hex_mode = true,
+            "-s" => simulate = true,
+            "--init-vault" => init_vault = true,
+            "--digest" => {
+                let val = args.next().unwrap_or_else(|| {
+                    eprintln!("--digest requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                });
+                digest = Digest::parse(&val).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                });
+            }
+            "--out-len" => {
+                let val = args.next().unwrap_or_else(|| {
+                    eprintln!("--out-len requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                });
+                out_len = Some(val.parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid --out-len '{}': expected a number\n\n{}", val, usage());
+                    std::process::exit(2);
+                }));
+            }
+            "--in-enc" => {
+                let val = args.next().unwrap_or_else(|| {
+                    eprintln!("--in-enc requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                });
+                in_enc = Some(Encoding::parse(&val).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                }));
+            }
+            "--out-enc" => {
+                let val = args.next().unwrap_or_else(|| {
+                    eprintln!("--out-enc requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                });
+                out_enc = Some(Encoding::parse(&val).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                }));
+            }
+            "-h" | "--help" => {
+                eprint!("{}", usage());
+                return;
+            }
+            "-V" | "--version" => {
+                println!("ykchalresp (yubi-rust-tool) {}", env!("CARGO_PKG_VERSION"));
+                return;
+            }
+            _ => {
+                // First non-flag is the challenge; pass the rest through unchanged
+                if challenge_arg.is_none() {
+                    challenge_arg = Some(a);
+                } else {
+                    // Unexpected extra arg; treat as error for clarity
+                    eprintln!("Unexpected argument: {}\n\n{}", a, usage());
+                    std::process::exit(2);
+                }
+            }
+        }
+    }
+
+    if init_vault {
+        match secret::run_init_vault(slot) {
+            Ok((path, removed_plaintext)) => {
+                println!("Encrypted secret for slot {} written to {}", slot, path.display());
+                match removed_plaintext {
+                    Some(plaintext_path) => {
+                        println!("Removed plaintext secret {}", plaintext_path.display());
+                    }
+                    None => {
+                        println!(
+                            "No plaintext slot{}.key found to remove (secret was loaded from \
+                             an env var or was already vaulted)",
+                            slot
+                        );
+                    }
+                }
+                return;
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if !simulate && digest != Digest::Sha1 {
+        eprintln!("--digest is only used in simulation mode (-s); hardware is always HMAC-SHA1");
+        std::process::exit(2);
+    }
+
+    // -x is shorthand for --in-enc hex --out-enc hex when those weren't set explicitly
+    let in_enc = in_enc.unwrap_or(if hex_mode { Encoding::Hex } else { Encoding::Raw });
+    let out_enc = out_enc.unwrap_or(if hex_mode { Encoding::Hex } else { Encoding::Modhex });
+    let out_len = out_len.unwrap_or_else(|| digest.default_output_len());
+
+    // In hardware mode we don't need to load the secret.
+
+    // Read challenge from arg or stdin
+    let challenge_bytes = match challenge_arg {
+        Some(s) => parse_challenge(&s, in_enc),
+        None => {
+            let mut buf = String::new();
+            if io::stdin().read_to_string(&mut buf).is_err() {
+                eprintln!("Failed to read challenge from stdin");
+                std::process::exit(1);
+            }
+            parse_challenge(buf.trim_end(), in_enc)
+        }
+    };
+
+    if simulate {
+        // Simulation: load secret and compute locally
+        let secret = match secret::load_slot_secret(slot) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+
+        let mac = digest.compute(&secret, &challenge_bytes, out_len).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        });
+        println!("{}", out_enc.encode(&mac));
+    } else {
+        // Hardware: invoke system ykchalresp tool and forward the challenge
+        match run_hardware(slot, &challenge_bytes, out_enc) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+fn parse_challenge(input: &str, in_enc: Encoding) -> Vec<u8> {
+    in_enc.decode(input).unwrap_or_else(|e| {
+        eprintln!("Invalid {:?} challenge: {}", in_enc, e);
+        std::process::exit(2);
+    })
+}
+
+// ----- Hardware via yubikey crate (OTP HMAC-SHA1 challenge-response) -----
+
+fn run_hardware(slot: u8, challenge: &[u8], out_enc: Encoding) -> Result<String, String> {
+    use yubikey_hmac_otp::config::{Config, Mode, Slot};
+    use yubikey_hmac_otp::Yubico;
+
+    // Discover a YubiKey
+    let mut y = Yubico::new();
+    let yk = y
+        .find_yubikey()
+        .map_err(|e| format!("Failed to find YubiKey: {}", e))?;
+
+    let slot = match slot {
+        1 => Slot::Slot1,
+        2 => Slot::Slot2,
+        _ => return Err("Invalid slot; must be 1 or 2".to_string()),
+    };
+
+    let conf = Config::new_from(yk).set_mode(Mode::Sha1).set_slot(slot);
+    let hmac = y
+        .challenge_response_hmac(challenge, conf)
+        .map_err(|e| format!("YubiKey HMAC-SHA1 challenge failed: {}", e))?;
+
+    Ok(out_enc.encode(&hmac[..]))
+}
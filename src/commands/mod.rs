@@ -0,0 +1,3 @@
+pub(crate) mod challenge;
+pub(crate) mod program;
+pub(crate) mod status;
@@ -0,0 +1,110 @@
+// ----- `program` subcommand: write an HMAC-SHA1 challenge-response config to a slot -----
+
+use crate::codec::Encoding;
+
+fn usage() -> &'static str {
+    "ykchalresp program -1|-2 --secret SECRET [--secret-enc hex|base32] [--touch]\n\n\
+    -1             program slot 1\n\
+    -2             program slot 2 (default)\n\
+    --secret SEC   the 20-byte HMAC-SHA1 secret to write\n\
+    --secret-enc ENC   encoding of --secret: hex|base32 (default: hex)\n\
+    --touch        require a touch before the key will respond to a challenge\n\
+    Writes an HMAC-SHA1 challenge-response configuration to the chosen slot\n\
+    of the first connected YubiKey, overwriting any existing configuration\n\
+    there.\n"
+}
+
+pub(crate) fn run(args: Vec<String>) {
+    let mut slot: u8 = 2;
+    let mut secret_arg: Option<String> = None;
+    let mut secret_enc = Encoding::Hex;
+    let mut touch = false;
+
+    let mut args = args.into_iter();
+    while let Some(a) = args.next() {
+        match a.as_str() {
+            "-1" => slot = 1,
+            "-2" => slot = 2,
+            "--touch" => touch = true,
+            "--secret" => {
+                secret_arg = Some(args.next().unwrap_or_else(|| {
+                    eprintln!("--secret requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                }));
+            }
+            "--secret-enc" => {
+                let val = args.next().unwrap_or_else(|| {
+                    eprintln!("--secret-enc requires a value\n\n{}", usage());
+                    std::process::exit(2);
+                });
+                secret_enc = Encoding::parse(&val).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(2);
+                });
+                if secret_enc != Encoding::Hex && secret_enc != Encoding::Base32 {
+                    eprintln!("--secret-enc must be hex or base32\n\n{}", usage());
+                    std::process::exit(2);
+                }
+            }
+            "-h" | "--help" => {
+                eprint!("{}", usage());
+                return;
+            }
+            _ => {
+                eprintln!("Unexpected argument: {}\n\n{}", a, usage());
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let secret_arg = secret_arg.unwrap_or_else(|| {
+        eprintln!("--secret is required\n\n{}", usage());
+        std::process::exit(2);
+    });
+    let secret_bytes = secret_enc.decode(&secret_arg).unwrap_or_else(|e| {
+        eprintln!("Invalid --secret: {}", e);
+        std::process::exit(2);
+    });
+    if secret_bytes.len() != 20 {
+        eprintln!(
+            "--secret must decode to exactly 20 bytes (got {})",
+            secret_bytes.len()
+        );
+        std::process::exit(2);
+    }
+
+    if let Err(e) = program_slot(slot, &secret_bytes, touch) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    println!("Slot {} programmed for HMAC-SHA1 challenge-response", slot);
+}
+
+fn program_slot(slot: u8, secret: &[u8], touch: bool) -> Result<(), String> {
+    use yubikey_hmac_otp::config::{Command, Config, Mode};
+    use yubikey_hmac_otp::configure::DeviceModeConfig;
+    use yubikey_hmac_otp::hmacmode::HmacKey;
+    use yubikey_hmac_otp::Yubico;
+
+    let mut y = Yubico::new();
+    let yk = y
+        .find_yubikey()
+        .map_err(|e| format!("Failed to find YubiKey: {}", e))?;
+
+    let command = match slot {
+        1 => Command::Configuration1,
+        2 => Command::Configuration2,
+        _ => return Err("Invalid slot; must be 1 or 2".to_string()),
+    };
+
+    // Slot selection for a *write* is carried by `command`, not `set_slot`
+    // (that only matters for challenge/response commands); see the crate's
+    // own `examples/configuration_hmac.rs`.
+    let conf = Config::new_from(yk).set_mode(Mode::Sha1).set_command(command);
+    let key = HmacKey::from_slice(secret);
+    let mut device_config = DeviceModeConfig::default();
+    device_config.challenge_response_hmac(&key, true, touch);
+
+    y.write_config(conf, &mut device_config)
+        .map_err(|e| format!("Failed to program slot {}: {}", slot, e))
+}
@@ -0,0 +1,65 @@
+// ----- `status` subcommand: enumerate connected keys and per-slot state -----
+
+fn usage() -> &'static str {
+    "ykchalresp status\n\n\
+    Lists connected YubiKeys and probes slot 1 and slot 2 of each with a\n\
+    throwaway HMAC-SHA1 challenge to report whether a slot looks configured\n\
+    for challenge-response or empty. This is a best-effort probe: the device\n\
+    protocol has no dedicated \"is this slot configured\" query, so the\n\
+    result is inferred from whether the challenge succeeds.\n"
+}
+
+pub(crate) fn run(args: Vec<String>) {
+    if let Some(a) = args.first() {
+        if a == "-h" || a == "--help" {
+            eprint!("{}", usage());
+            return;
+        }
+        eprintln!("Unexpected argument: {}\n\n{}", a, usage());
+        std::process::exit(2);
+    }
+
+    match list_and_probe() {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn list_and_probe() -> Result<String, String> {
+    use yubikey_hmac_otp::config::{Config, Mode, Slot};
+    use yubikey_hmac_otp::Yubico;
+
+    let mut y = Yubico::new();
+    let keys = y
+        .find_all_yubikeys()
+        .map_err(|e| format!("Failed to enumerate YubiKeys: {}", e))?;
+
+    let mut out = String::new();
+    for yk in keys {
+        let label = match (&yk.name, yk.serial) {
+            (Some(name), Some(serial)) => format!("{} (serial {})", name, serial),
+            (Some(name), None) => name.clone(),
+            (None, Some(serial)) => format!("serial {}", serial),
+            (None, None) => "unknown YubiKey".to_string(),
+        };
+        out.push_str(&format!("{}\n", label));
+
+        for (slot_num, slot) in [(1u8, Slot::Slot1), (2u8, Slot::Slot2)] {
+            let conf = Config::new_from(yk.clone()).set_mode(Mode::Sha1).set_slot(slot);
+            let probe_challenge = [0u8; 1];
+            let state = match y.challenge_response_hmac(&probe_challenge, conf) {
+                Ok(_) => "configured",
+                Err(_) => "empty or not HMAC-SHA1",
+            };
+            out.push_str(&format!("  slot {}: {}\n", slot_num, state));
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("No YubiKeys found\n");
+    }
+    Ok(out)
+}
@@ -0,0 +1,304 @@
+// ----- Slot secret resolution (env, plaintext file, encrypted vault) -----
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::codec::from_hex;
+use crate::vault;
+
+pub(crate) fn load_slot_secret(slot: u8) -> Result<Vec<u8>, String> {
+    // 1) Env var takes precedence
+    let env_name = match slot {
+        1 => "YKCHALRESP_SLOT1_KEY",
+        2 => "YKCHALRESP_SLOT2_KEY",
+        _ => return Err("Invalid slot; must be 1 or 2".to_string()),
+    };
+    if let Ok(val) = env::var(env_name) {
+        return from_hex(&val).map_err(|e| format!("{} contains invalid hex: {}", env_name, e));
+    }
+
+    let key_file = match slot {
+        1 => "slot1.key",
+        2 => "slot2.key",
+        _ => unreachable!(),
+    };
+    let dirs = config_dirs();
+    if dirs.is_empty() {
+        return Err(
+            "Cannot resolve a config directory (no XDG_CONFIG_HOME/HOME on Unix, \
+             no APPDATA/USERPROFILE on Windows)"
+                .to_string(),
+        );
+    }
+
+    // 2) Encrypted vault in any candidate config directory, if present
+    if let Some(enc_path) = find_vault_path(slot) {
+        return load_vault_secret(&enc_path);
+    }
+
+    // 3) Plaintext config file in any candidate config directory
+    for dir in &dirs {
+        let path = dir.join(key_file);
+        if let Ok(content) = fs::read_to_string(&path) {
+            let trimmed = content.trim();
+            return from_hex(trimmed)
+                .map_err(|e| format!("{} contains invalid hex: {}", path.display(), e));
+        }
+    }
+
+    let searched: Vec<String> = dirs.iter().map(|d| d.join(key_file).display().to_string()).collect();
+    Err(format!(
+        "Missing secret for slot {}. Set {}, or create a hex key at one of: {} \
+         (an encrypted {}.enc next to it also works).",
+        slot,
+        env_name,
+        searched.join(", "),
+        key_file
+    ))
+}
+
+/// Return the path to slot `slot`'s encrypted vault file, searching the same
+/// candidate config directories as [`load_slot_secret`], if one exists.
+fn find_vault_path(slot: u8) -> Option<PathBuf> {
+    let enc_file = match slot {
+        1 => "slot1.key.enc",
+        2 => "slot2.key.enc",
+        _ => return None,
+    };
+    config_dirs().into_iter().map(|d| d.join(enc_file)).find(|p| p.exists())
+}
+
+/// Encrypt the slot's secret into a new `slotN.key.enc` vault file.
+///
+/// On success, also removes any plaintext `slotN.key` the secret was loaded
+/// from (searched the same way as [`load_slot_secret`]) so a leftover
+/// plaintext copy doesn't defeat the point of vaulting it. Returns the path
+/// to the new vault file and, if one was found and removed, the path to the
+/// deleted plaintext file.
+pub(crate) fn run_init_vault(slot: u8) -> Result<(PathBuf, Option<PathBuf>), String> {
+    // If the slot is already vaulted, the passphrase we read unlocks the
+    // existing vault, so reuse it to re-seal instead of prompting again:
+    // a second prompt would either surprise an interactive user with a
+    // silent passphrase change, or, fed from a pipe, hit EOF and re-seal
+    // the vault under an empty passphrase.
+    let existing_vault = find_vault_path(slot);
+    let (secret, passphrase) = match &existing_vault {
+        Some(path) => {
+            let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let passphrase = read_passphrase()?;
+            let secret = vault::decrypt_secret(&passphrase, &data)
+                .map_err(|e| format!("{} (file: {})", e, path.display()))?;
+            (secret, passphrase)
+        }
+        None => {
+            let secret = load_slot_secret(slot)?;
+            let passphrase = read_passphrase()?;
+            (secret, passphrase)
+        }
+    };
+    let sealed = vault::encrypt_secret(&passphrase, &secret)?;
+
+    let enc_file = match slot {
+        1 => "slot1.key.enc",
+        2 => "slot2.key.enc",
+        _ => return Err("Invalid slot; must be 1 or 2".to_string()),
+    };
+    // Re-seal in place if the slot was already vaulted, so a preferred
+    // config dir resolving differently than it did at vault-creation time
+    // (e.g. XDG_CONFIG_HOME set later) can't leave a stray, still-decryptable
+    // copy of the secret behind in the old directory.
+    let path = match existing_vault {
+        Some(path) => path,
+        None => {
+            let dir = config_dirs()
+                .into_iter()
+                .next()
+                .ok_or_else(|| "Cannot resolve a config directory to write to".to_string())?;
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            dir.join(enc_file)
+        }
+    };
+    fs::write(&path, sealed).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let removed = remove_plaintext_key_file(slot)?;
+    Ok((path, removed))
+}
+
+/// Find and delete the plaintext `slotN.key` file (if any) across the same
+/// candidate config directories `load_slot_secret` searches.
+fn remove_plaintext_key_file(slot: u8) -> Result<Option<PathBuf>, String> {
+    let key_file = match slot {
+        1 => "slot1.key",
+        2 => "slot2.key",
+        _ => return Err("Invalid slot; must be 1 or 2".to_string()),
+    };
+    for dir in config_dirs() {
+        let path = dir.join(key_file);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+fn load_vault_secret(path: &PathBuf) -> Result<Vec<u8>, String> {
+    let data = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let passphrase = read_passphrase()?;
+    vault::decrypt_secret(&passphrase, &data)
+        .map_err(|e| format!("{} (file: {})", e, path.display()))
+}
+
+pub(crate) fn read_passphrase() -> Result<String, String> {
+    if let Ok(p) = env::var("YKCHALRESP_PASSPHRASE") {
+        return Ok(p);
+    }
+    eprint!("Passphrase for encrypted key vault: ");
+    io::stderr().flush().ok();
+    let mut line = String::new();
+    io::stdin()
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read passphrase: {}", e))?;
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Ordered list of candidate `ykchalresp` config directories to search for
+/// `slotN.key`/`slotN.key.enc`, most-preferred first. The first entry is
+/// also where `run_init_vault` writes new vault files.
+///
+/// Unix: `$XDG_CONFIG_HOME/ykchalresp`, falling back to `$HOME/.config/ykchalresp`.
+/// Windows: `%APPDATA%\ykchalresp`, falling back to `%USERPROFILE%\.config\ykchalresp`.
+fn config_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if cfg!(windows) {
+        if let Ok(appdata) = env::var("APPDATA") {
+            dirs.push(PathBuf::from(appdata).join("ykchalresp"));
+        }
+        if let Ok(profile) = env::var("USERPROFILE") {
+            dirs.push(PathBuf::from(profile).join(".config").join("ykchalresp"));
+        }
+    } else {
+        if let Ok(xdg) = env::var("XDG_CONFIG_HOME") {
+            if !xdg.is_empty() {
+                dirs.push(PathBuf::from(xdg).join("ykchalresp"));
+            }
+        }
+        if let Ok(home) = env::var("HOME") {
+            let fallback = PathBuf::from(home).join(".config").join("ykchalresp");
+            if !dirs.contains(&fallback) {
+                dirs.push(fallback);
+            }
+        }
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // `config_dirs` reads process-global env vars, so serialize the tests
+    // that touch them to avoid one test observing another's in-flight state.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    struct EnvGuard {
+        vars: Vec<(&'static str, Option<String>)>,
+    }
+
+    impl EnvGuard {
+        fn set(pairs: &[(&'static str, Option<&str>)]) -> EnvGuard {
+            let vars = pairs
+                .iter()
+                .map(|(name, _)| (*name, env::var(name).ok()))
+                .collect();
+            for (name, value) in pairs {
+                match value {
+                    Some(v) => env::set_var(name, v),
+                    None => env::remove_var(name),
+                }
+            }
+            EnvGuard { vars }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for (name, value) in &self.vars {
+                match value {
+                    Some(v) => env::set_var(name, v),
+                    None => env::remove_var(name),
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn config_dirs_prefers_xdg_over_home_fallback() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let _guard = EnvGuard::set(&[
+            ("XDG_CONFIG_HOME", Some("/xdg")),
+            ("HOME", Some("/home/alice")),
+        ]);
+        assert_eq!(
+            config_dirs(),
+            vec![
+                PathBuf::from("/xdg/ykchalresp"),
+                PathBuf::from("/home/alice/.config/ykchalresp"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn config_dirs_falls_back_to_home_without_xdg() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let _guard = EnvGuard::set(&[
+            ("XDG_CONFIG_HOME", None),
+            ("HOME", Some("/home/alice")),
+        ]);
+        assert_eq!(
+            config_dirs(),
+            vec![PathBuf::from("/home/alice/.config/ykchalresp")]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn config_dirs_ignores_empty_xdg_var() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let _guard = EnvGuard::set(&[
+            ("XDG_CONFIG_HOME", Some("")),
+            ("HOME", Some("/home/alice")),
+        ]);
+        assert_eq!(
+            config_dirs(),
+            vec![PathBuf::from("/home/alice/.config/ykchalresp")]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn config_dirs_dedups_when_xdg_and_home_fallback_coincide() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let _guard = EnvGuard::set(&[
+            ("XDG_CONFIG_HOME", Some("/home/alice/.config")),
+            ("HOME", Some("/home/alice")),
+        ]);
+        assert_eq!(
+            config_dirs(),
+            vec![PathBuf::from("/home/alice/.config/ykchalresp")]
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn config_dirs_empty_without_xdg_or_home() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let _guard = EnvGuard::set(&[("XDG_CONFIG_HOME", None), ("HOME", None)]);
+        assert_eq!(config_dirs(), Vec::<PathBuf>::new());
+    }
+}